@@ -0,0 +1,352 @@
+//! An opt-in client-side cache built on RESP3 server-assisted caching
+//! (`CLIENT TRACKING`).
+//!
+//! The server pushes an `invalidate` frame (see the push handling in
+//! [`super`]) whenever a tracked key changes, so the cache never needs to
+//! poll or guess at a TTL: an entry simply lives until the server tells us
+//! it's stale. `setup_connection_with_observability` enables tracking during
+//! connection setup when a [`ClientSideCacheConfig`] is supplied and the
+//! connection uses RESP3; callers evict on push via
+//! [`ClientSideCache::handle_invalidation`] and consult the cache through
+//! [`ClientSideCache::get_or_fetch`] / [`ClientSideCache::get_or_fetch_multi`].
+
+use crate::aio::{invalidated_keys_from_push, ConnectionLike};
+use crate::cmd::Cmd;
+use crate::types::{ErrorKind, RedisError, RedisResult, Value};
+use crate::PushKind;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+/// How the server groups keys for invalidation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TrackingMode {
+    /// Only caches keys this connection itself has read.
+    Default,
+    /// Caches any key under the given prefixes, regardless of which
+    /// connection read it (`CLIENT TRACKING ON BCAST PREFIX ...`).
+    Bcast { prefixes: Vec<Vec<u8>> },
+}
+
+/// Configuration for the opt-in client-side cache.
+#[derive(Clone, Debug)]
+pub struct ClientSideCacheConfig {
+    /// Tracking mode to request via `CLIENT TRACKING ON` during setup.
+    pub mode: TrackingMode,
+    /// Maximum number of entries kept before the least-recently-used entry
+    /// is evicted to make room for a new one.
+    pub max_entries: usize,
+    /// Command names (case-insensitive) whose replies are safe to cache.
+    pub cacheable_commands: Vec<&'static str>,
+}
+
+impl Default for ClientSideCacheConfig {
+    fn default() -> Self {
+        ClientSideCacheConfig {
+            mode: TrackingMode::Default,
+            max_entries: 10_000,
+            cacheable_commands: vec!["GET", "MGET", "HGETALL"],
+        }
+    }
+}
+
+impl ClientSideCacheConfig {
+    /// Returns whether `command_name` is configured as cacheable.
+    pub fn is_cacheable(&self, command_name: &str) -> bool {
+        self.cacheable_commands
+            .iter()
+            .any(|c| c.eq_ignore_ascii_case(command_name))
+    }
+}
+
+struct CacheStore {
+    entries: HashMap<Vec<u8>, Value>,
+    lru: VecDeque<Vec<u8>>,
+    // Bumped whenever a key is invalidated so that a fetch started before
+    // the invalidation, but completing after it, can tell its result is
+    // stale and must not resurrect the key. `flush_generation` plays the
+    // same role for `invalidate-all` (a nil payload).
+    generations: HashMap<Vec<u8>, u64>,
+    flush_generation: u64,
+}
+
+impl CacheStore {
+    fn new() -> Self {
+        CacheStore {
+            entries: HashMap::new(),
+            lru: VecDeque::new(),
+            generations: HashMap::new(),
+            flush_generation: 0,
+        }
+    }
+
+    fn touch(&mut self, key: &[u8]) {
+        if let Some(pos) = self.lru.iter().position(|k| k == key) {
+            self.lru.remove(pos);
+        }
+        self.lru.push_back(key.to_vec());
+    }
+
+    fn get(&mut self, key: &[u8]) -> Option<Value> {
+        let value = self.entries.get(key).cloned();
+        if value.is_some() {
+            self.touch(key);
+        }
+        value
+    }
+
+    /// The generation a fetch for `key` must still match at store-time for
+    /// its result to be accepted.
+    fn current_generation(&self, key: &[u8]) -> u64 {
+        self.generations
+            .get(key)
+            .copied()
+            .unwrap_or(0)
+            .max(self.flush_generation)
+    }
+
+    fn put(&mut self, key: Vec<u8>, value: Value, generation: u64, max_entries: usize) {
+        if generation != self.current_generation(&key) {
+            // Invalidated (or flushed) since the fetch started: a late
+            // store must not resurrect a value the server already
+            // considers stale.
+            return;
+        }
+        if !self.entries.contains_key(&key) && self.entries.len() >= max_entries {
+            if let Some(evicted) = self.lru.pop_front() {
+                self.entries.remove(&evicted);
+            }
+        }
+        self.touch(&key);
+        self.entries.insert(key, value);
+    }
+
+    fn invalidate(&mut self, key: &[u8]) {
+        self.entries.remove(key);
+        if let Some(pos) = self.lru.iter().position(|k| k == key) {
+            self.lru.remove(pos);
+        }
+        let next = self.current_generation(key) + 1;
+        self.generations.insert(key.to_vec(), next);
+    }
+
+    fn flush(&mut self) {
+        self.entries.clear();
+        self.lru.clear();
+        // Move past every per-key generation before dropping them, so no
+        // key's current generation can fall back to a value a fetch in
+        // flight already holds.
+        let highest = self.generations.values().copied().max().unwrap_or(0);
+        self.generations.clear();
+        self.flush_generation = highest.max(self.flush_generation) + 1;
+    }
+}
+
+/// A shared, thread-safe client-side cache. Cheap to clone - every clone
+/// refers to the same underlying store, so it can be handed to each
+/// connection task alongside a cloned [`super::SharedConnectionLike`] handle.
+#[derive(Clone)]
+pub struct ClientSideCache {
+    store: Arc<Mutex<CacheStore>>,
+    config: Arc<ClientSideCacheConfig>,
+}
+
+impl ClientSideCache {
+    /// Creates a new, empty cache using `config`.
+    pub fn new(config: ClientSideCacheConfig) -> Self {
+        ClientSideCache {
+            store: Arc::new(Mutex::new(CacheStore::new())),
+            config: Arc::new(config),
+        }
+    }
+
+    /// The configuration this cache was created with.
+    pub fn config(&self) -> &ClientSideCacheConfig {
+        &self.config
+    }
+
+    /// Applies a `CLIENT TRACKING` invalidation push. `keys` is `None` for
+    /// a flush-all notification (a nil payload), which clears the entire
+    /// cache.
+    pub fn handle_invalidation(&self, keys: Option<&[Vec<u8>]>) {
+        let mut store = self.store.lock().unwrap();
+        match keys {
+            None => store.flush(),
+            Some(keys) => {
+                for key in keys {
+                    store.invalidate(key);
+                }
+            }
+        }
+    }
+
+    /// Applies a decoded RESP3 push frame to the cache - a no-op unless
+    /// `kind` is a `CLIENT TRACKING` invalidation, in which case it's
+    /// equivalent to `handle_invalidation`. The connection's push-frame
+    /// receive loop should call this for every push frame it decodes, the
+    /// same way it already recognizes pub/sub pushes.
+    pub fn apply_push(&self, kind: &PushKind, data: &[Value]) {
+        if let Some(keys) = invalidated_keys_from_push(kind, data) {
+            self.handle_invalidation(keys.as_deref());
+        }
+    }
+
+    /// Returns `key`'s cached value if present, otherwise runs `cmd` on
+    /// `con` and caches its reply under `key`.
+    pub async fn get_or_fetch<C>(&self, con: &mut C, key: &[u8], cmd: &Cmd) -> RedisResult<Value>
+    where
+        C: ConnectionLike,
+    {
+        if let Some(cached) = self.store.lock().unwrap().get(key) {
+            return Ok(cached);
+        }
+        let generation = self.store.lock().unwrap().current_generation(key);
+        let value = con.req_packed_command(cmd).await?;
+        self.store.lock().unwrap().put(
+            key.to_vec(),
+            value.clone(),
+            generation,
+            self.config.max_entries,
+        );
+        Ok(value)
+    }
+
+    /// `MGET`-shaped lookup: returns one value per key in `keys`. When every
+    /// key is cached the values come from the cache and `cmd` is not sent;
+    /// when at least one key misses, `cmd` is sent and its reply supplies
+    /// (and is cached for) all keys, cached ones included. `cmd`'s reply
+    /// must be a `Value::Array` with one entry per key, in the same order as
+    /// `keys`.
+    pub async fn get_or_fetch_multi<C>(
+        &self,
+        con: &mut C,
+        keys: &[Vec<u8>],
+        cmd: &Cmd,
+    ) -> RedisResult<Vec<Value>>
+    where
+        C: ConnectionLike,
+    {
+        {
+            let mut store = self.store.lock().unwrap();
+            let cached: Option<Vec<Value>> = keys.iter().map(|k| store.get(k)).collect();
+            if let Some(cached) = cached {
+                return Ok(cached);
+            }
+        }
+        let generations: Vec<u64> = {
+            let store = self.store.lock().unwrap();
+            keys.iter().map(|k| store.current_generation(k)).collect()
+        };
+        let reply = con.req_packed_command(cmd).await?;
+        let values = match &reply {
+            Value::Array(values) if values.len() == keys.len() => values.clone(),
+            _ => {
+                return Err(RedisError::from((
+                    ErrorKind::TypeError,
+                    "Response not compatible with the requested command",
+                    format!(
+                        "get_or_fetch_multi expected an array of {} replies, got: {reply:?}",
+                        keys.len()
+                    ),
+                )))
+            }
+        };
+        let mut store = self.store.lock().unwrap();
+        for ((key, value), generation) in keys.iter().zip(values.iter()).zip(generations) {
+            store.put(
+                key.clone(),
+                value.clone(),
+                generation,
+                self.config.max_entries,
+            );
+        }
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn put_skips_store_when_key_was_invalidated_mid_fetch() {
+        let mut store = CacheStore::new();
+        let generation = store.current_generation(b"k");
+        store.invalidate(b"k");
+        store.put(b"k".to_vec(), Value::Okay, generation, 10);
+        assert!(store.get(b"k").is_none());
+    }
+
+    #[test]
+    fn put_skips_store_when_flushed_mid_fetch() {
+        let mut store = CacheStore::new();
+        let generation = store.current_generation(b"k");
+        store.flush();
+        store.put(b"k".to_vec(), Value::Okay, generation, 10);
+        assert!(store.get(b"k").is_none());
+    }
+
+    #[test]
+    fn put_evicts_least_recently_used_entry_first() {
+        let mut store = CacheStore::new();
+        for key in [b"a".to_vec(), b"b".to_vec(), b"c".to_vec()] {
+            let generation = store.current_generation(&key);
+            store.put(key, Value::Okay, generation, 2);
+        }
+        // "a" was inserted first and never touched again, so it's evicted
+        // once the third key pushes the store past max_entries.
+        assert!(store.get(b"a").is_none());
+        assert!(store.get(b"b").is_some());
+        assert!(store.get(b"c").is_some());
+    }
+
+    #[test]
+    fn flush_clears_all_entries() {
+        let mut store = CacheStore::new();
+        for key in [b"a".to_vec(), b"b".to_vec()] {
+            let generation = store.current_generation(&key);
+            store.put(key, Value::Okay, generation, 10);
+        }
+        store.flush();
+        assert!(store.get(b"a").is_none());
+        assert!(store.get(b"b").is_none());
+    }
+
+    #[test]
+    fn apply_push_evicts_key_on_invalidate() {
+        let cache = ClientSideCache::new(ClientSideCacheConfig::default());
+        cache
+            .store
+            .lock()
+            .unwrap()
+            .put(b"k".to_vec(), Value::Okay, 0, 10);
+        cache.apply_push(
+            &PushKind::Invalidate,
+            &[Value::Array(vec![Value::BulkString(b"k".to_vec())])],
+        );
+        assert!(cache.store.lock().unwrap().get(b"k").is_none());
+    }
+
+    #[test]
+    fn apply_push_flushes_on_nil_payload() {
+        let cache = ClientSideCache::new(ClientSideCacheConfig::default());
+        cache
+            .store
+            .lock()
+            .unwrap()
+            .put(b"k".to_vec(), Value::Okay, 0, 10);
+        cache.apply_push(&PushKind::Invalidate, &[Value::Nil]);
+        assert!(cache.store.lock().unwrap().get(b"k").is_none());
+    }
+
+    #[test]
+    fn apply_push_ignores_non_invalidate_push() {
+        let cache = ClientSideCache::new(ClientSideCacheConfig::default());
+        cache
+            .store
+            .lock()
+            .unwrap()
+            .put(b"k".to_vec(), Value::Okay, 0, 10);
+        cache.apply_push(&PushKind::Message, &[Value::Nil]);
+        assert!(cache.store.lock().unwrap().get(b"k").is_some());
+    }
+}
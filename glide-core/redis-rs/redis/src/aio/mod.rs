@@ -12,9 +12,10 @@ use crate::PushKind;
 use ::tokio::io::{AsyncRead, AsyncWrite};
 use async_trait::async_trait;
 use futures_util::Future;
+use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
 #[cfg(unix)]
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use std::time::Duration;
 
@@ -43,6 +44,29 @@ pub(crate) trait RedisRuntime: AsyncStream + Send + Sync + Sized + 'static {
     #[cfg(unix)]
     async fn connect_unix(path: &Path) -> RedisResult<Self>;
 
+    /// Performs a UNIX connection wrapped in TLS
+    ///
+    /// Defaults to an error so existing `RedisRuntime` implementors keep
+    /// compiling without change; a runtime that can wrap a Unix socket in
+    /// TLS overrides this. The `tokio-comp` runtime is meant to be the
+    /// primary override: `aio/tokio.rs` (declared above as `pub mod tokio`)
+    /// is absent from this checkout, so its `RedisRuntime` impl can't be
+    /// edited here without inventing that runtime's connector code from
+    /// scratch. Whoever has that file needs to add a real
+    /// `connect_unix_tls` there, wrapping its `UnixStream` in TLS the same
+    /// way its `connect_tcp_tls` wraps a `TcpStream`.
+    #[cfg(unix)]
+    async fn connect_unix_tls(
+        _path: &Path,
+        _insecure: bool,
+        _tls_params: &Option<TlsConnParams>,
+    ) -> RedisResult<Self> {
+        Err(RedisError::from((
+            ErrorKind::InvalidClientConfig,
+            "This runtime does not support TLS over a Unix socket",
+        )))
+    }
+
     fn spawn(f: impl Future<Output = ()> + Send + 'static);
 
     fn boxed(self) -> Pin<Box<dyn AsyncStream + Send + Sync>> {
@@ -54,6 +78,68 @@ pub(crate) trait RedisRuntime: AsyncStream + Send + Sync + Sized + 'static {
 pub trait AsyncStream: AsyncRead + AsyncWrite {}
 impl<S> AsyncStream for S where S: AsyncRead + AsyncWrite {}
 
+/// A connection target expressible as a single serializable value, covering
+/// every connect method [`RedisRuntime`] offers. This lets topology config
+/// (e.g. a set of node addresses) be persisted and restored without having
+/// to reconstruct it from separate host/port/socket-path/TLS fields.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConnectionAddr {
+    /// A plain TCP connection to `host`:`port`.
+    Tcp { host: String, port: u16 },
+    /// A TCP connection wrapped in TLS. `insecure` disables certificate
+    /// verification.
+    TcpTls { host: String, port: u16, insecure: bool },
+    /// A Unix domain socket at `path`.
+    #[cfg(unix)]
+    Unix { path: PathBuf },
+    /// A Unix domain socket wrapped in TLS. `insecure` disables certificate
+    /// verification.
+    #[cfg(unix)]
+    UnixTls { path: PathBuf, insecure: bool },
+}
+
+impl ConnectionAddr {
+    fn resolve_tcp(host: &str, port: u16) -> RedisResult<SocketAddr> {
+        use std::net::ToSocketAddrs;
+        (host, port)
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| {
+                RedisError::from((
+                    ErrorKind::InvalidClientConfig,
+                    "No addresses found for host",
+                ))
+            })
+    }
+
+    /// Connects to this address using the matching [`RedisRuntime`] method.
+    /// `tls_params` is only consulted for the TLS variants.
+    pub(crate) async fn connect<C: RedisRuntime>(
+        &self,
+        tls_params: &Option<TlsConnParams>,
+    ) -> RedisResult<C> {
+        match self {
+            ConnectionAddr::Tcp { host, port } => {
+                C::connect_tcp(Self::resolve_tcp(host, *port)?).await
+            }
+            ConnectionAddr::TcpTls {
+                host,
+                port,
+                insecure,
+            } => {
+                C::connect_tcp_tls(host, Self::resolve_tcp(host, *port)?, *insecure, tls_params)
+                    .await
+            }
+            #[cfg(unix)]
+            ConnectionAddr::Unix { path } => C::connect_unix(path).await,
+            #[cfg(unix)]
+            ConnectionAddr::UnixTls { path, insecure } => {
+                C::connect_unix_tls(path, *insecure, tls_params).await
+            }
+        }
+    }
+}
+
 /// An async abstraction over connections.
 pub trait ConnectionLike {
     /// Sends an already encoded (packed) command into the TCP socket and
@@ -94,6 +180,76 @@ pub trait ConnectionLike {
     fn set_az(&mut self, _az: Option<String>) {}
 }
 
+/// An async abstraction over connections that can be driven through a shared
+/// reference rather than `&mut self`.
+///
+/// [`ConnectionLike`] requires `&mut self` for every command, which forces
+/// callers to either serialize access behind a lock or clone the connection
+/// on every call. Types like the multiplexed connection and connection
+/// manager are already internally `Clone` and safe to drive concurrently -
+/// cloning them is cheap because it only clones a handle to the shared
+/// dispatcher, not the socket. This trait exposes that concurrency model
+/// directly, so a single connection handle can be stored in shared app state
+/// and used from many tasks behind `&self`.
+///
+/// A blanket implementation covers every `ConnectionLike` type that is also
+/// `Clone + Send + Sync + 'static`, so most callers never need to implement
+/// this by hand.
+pub trait SharedConnectionLike {
+    /// Sends an already encoded (packed) command into the TCP socket and
+    /// reads the single response from it.
+    fn req_packed_command<'a>(&'a self, cmd: &'a Cmd) -> RedisFuture<'a, Value>;
+
+    /// Sends multiple already encoded (packed) command into the TCP socket
+    /// and reads `count` responses from it. See
+    /// [`ConnectionLike::req_packed_commands`] for the caveats around
+    /// `offset` & `count`.
+    #[doc(hidden)]
+    fn req_packed_commands<'a>(
+        &'a self,
+        cmd: &'a crate::Pipeline,
+        offset: usize,
+        count: usize,
+        pipeline_retry_strategy: Option<PipelineRetryStrategy>,
+    ) -> RedisFuture<'a, Vec<Value>>;
+}
+
+impl<C> SharedConnectionLike for C
+where
+    C: ConnectionLike + Clone + Send + Sync + 'static,
+{
+    fn req_packed_command<'a>(&'a self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        Box::pin(async move {
+            let mut conn = self.clone();
+            // Qualified so this calls `ConnectionLike::req_packed_command`:
+            // an unqualified `conn.req_packed_command(cmd)` would resolve
+            // right back to this same `SharedConnectionLike` method and
+            // recurse forever.
+            ConnectionLike::req_packed_command(&mut conn, cmd).await
+        })
+    }
+
+    fn req_packed_commands<'a>(
+        &'a self,
+        cmd: &'a crate::Pipeline,
+        offset: usize,
+        count: usize,
+        pipeline_retry_strategy: Option<PipelineRetryStrategy>,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        Box::pin(async move {
+            let mut conn = self.clone();
+            ConnectionLike::req_packed_commands(
+                &mut conn,
+                cmd,
+                offset,
+                count,
+                pipeline_retry_strategy,
+            )
+            .await
+        })
+    }
+}
+
 /// Implements ability to notify about disconnection events
 #[async_trait]
 pub trait DisconnectNotifier: Send + Sync {
@@ -113,6 +269,249 @@ impl Clone for Box<dyn DisconnectNotifier> {
     }
 }
 
+/// The post-connect setup stage a `SetupFailed` event was raised from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SetupStage {
+    /// `HELLO` (RESP3) or `AUTH` (RESP2).
+    Hello,
+    /// The batched `SELECT` / `CLIENT SETNAME` / `CLIENT TRACKING` setup
+    /// commands run via [`run_post_hello_setup`].
+    PostHelloSetup,
+    /// The `INFO` lookup used to discover the connection's availability
+    /// zone.
+    AzDiscovery,
+    /// Restoring a pub/sub subscription after a reconnect.
+    Resubscribe,
+}
+
+/// A point-in-time transition in a connection's lifecycle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    /// A (re)connect attempt is starting.
+    Connecting,
+    /// The connection completed setup and is ready to serve commands.
+    Connected,
+    /// A previously established connection is being retried after a drop.
+    ///
+    /// Emitted by the reconnect loop in `connection_manager`, which isn't
+    /// part of this module - `setup_connection` only ever sees a fresh
+    /// connection and has no attempt counter of its own to report.
+    Reconnecting {
+        /// The number of reconnect attempts made so far, including this one.
+        attempt: u32,
+    },
+    /// The connection was lost.
+    ///
+    /// Emitted from the transport-level disconnect path (e.g. a read/write
+    /// error or the `DisconnectNotifier` callback), not from this module -
+    /// `setup_connection` only runs on a connection that is already up.
+    Disconnected {
+        /// A human-readable description of why the connection was lost.
+        reason: String,
+    },
+    /// The transport connected, but setup failed at `stage` - surfaced
+    /// instead of letting every setup failure collapse into one generic
+    /// `ResponseError`.
+    SetupFailed {
+        /// Which setup stage failed.
+        stage: SetupStage,
+    },
+}
+
+/// Observes connection lifecycle transitions.
+///
+/// Where [`DisconnectNotifier`] only signals that a disconnect happened,
+/// this trait receives every lifecycle transition an application may care
+/// about. `setup_connection_with_observability` in this module emits
+/// [`ConnectionEvent::Connecting`],
+/// [`ConnectionEvent::Connected`] and [`ConnectionEvent::SetupFailed`];
+/// [`ConnectionEvent::Reconnecting`] and [`ConnectionEvent::Disconnected`]
+/// come from the reconnect loop and disconnect path outside this module
+/// (`connection_manager` and the transport layer), which a caller is
+/// expected to wire up to the same listener. Together they let applications
+/// drive metrics, circuit breakers, and health endpoints off real state
+/// changes instead of polling [`ConnectionLike::is_closed`].
+pub trait ConnectionEventListener: Send + Sync {
+    /// Called for every lifecycle transition.
+    fn on_event(&self, event: ConnectionEvent);
+}
+
+// Takes an already computed `result`, and if it's an `Err`, reports `stage`
+// to `listener` before handing the error back unchanged.
+async fn report_setup_failure<T>(
+    listener: Option<&dyn ConnectionEventListener>,
+    stage: SetupStage,
+    result: RedisResult<T>,
+) -> RedisResult<T> {
+    if result.is_err() {
+        if let Some(listener) = listener {
+            listener.on_event(ConnectionEvent::SetupFailed { stage });
+        }
+    }
+    result
+}
+
+/// A [`SetupError`] variant, without its payload, that survives the
+/// conversion into [`RedisError`] so callers can still branch on it - e.g.
+/// retrying a transient [`SetupErrorKind::ResubscribeFailed`] while treating
+/// [`SetupErrorKind::AuthFailed`] as fatal.
+///
+/// `redis-rs`'s `RedisError` has no slot for an arbitrary typed payload, so
+/// [`SetupError`] can't be embedded in it directly; [`SetupError::classify`]
+/// recovers this instead from a marker that `From<SetupError>` writes into
+/// the `RedisError`'s detail string.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SetupErrorKind {
+    /// RESP2 `AUTH` was rejected. A failed RESP3 `HELLO` is returned as the
+    /// server reported it and is not classified.
+    AuthFailed,
+    /// `SELECT <db>` was refused.
+    SelectDbRefused,
+    /// `CLIENT SETNAME` was refused.
+    SetNameRefused,
+    /// The `INFO` lookup used for availability-zone discovery failed.
+    AzDiscoveryFailed,
+    /// `CLIENT TRACKING ON` was refused while enabling the client-side
+    /// cache.
+    ClientTrackingRefused,
+    /// Restoring a pub/sub subscription after a reconnect failed.
+    ResubscribeFailed,
+}
+
+impl SetupErrorKind {
+    fn tag(&self) -> &'static str {
+        match self {
+            SetupErrorKind::AuthFailed => "auth_failed",
+            SetupErrorKind::SelectDbRefused => "select_db_refused",
+            SetupErrorKind::SetNameRefused => "set_name_refused",
+            SetupErrorKind::AzDiscoveryFailed => "az_discovery_failed",
+            SetupErrorKind::ClientTrackingRefused => "client_tracking_refused",
+            SetupErrorKind::ResubscribeFailed => "resubscribe_failed",
+        }
+    }
+
+    fn from_tag(tag: &str) -> Option<SetupErrorKind> {
+        Some(match tag {
+            "auth_failed" => SetupErrorKind::AuthFailed,
+            "select_db_refused" => SetupErrorKind::SelectDbRefused,
+            "set_name_refused" => SetupErrorKind::SetNameRefused,
+            "az_discovery_failed" => SetupErrorKind::AzDiscoveryFailed,
+            "client_tracking_refused" => SetupErrorKind::ClientTrackingRefused,
+            "resubscribe_failed" => SetupErrorKind::ResubscribeFailed,
+            _ => return None,
+        })
+    }
+}
+
+/// Distinguishes precisely why connection setup failed, instead of letting
+/// every case collapse into the same generic `ResponseError`.
+///
+/// Converts into a [`RedisError`] carrying the exact [`ErrorKind`] each case
+/// already used, so existing code that only matches on `ErrorKind` is
+/// unaffected; callers that want the extra precision can recover the
+/// originating variant (as a [`SetupErrorKind`]) via [`SetupError::classify`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SetupError {
+    /// RESP2 `AUTH` was rejected. A failed RESP3 `HELLO` is returned as the
+    /// server reported it, not as this variant.
+    AuthFailed,
+    /// `SELECT <db>` was refused.
+    SelectDbRefused,
+    /// `CLIENT SETNAME` was refused.
+    SetNameRefused,
+    /// The `INFO` lookup used for availability-zone discovery failed.
+    AzDiscoveryFailed,
+    /// `CLIENT TRACKING ON` was refused while enabling the client-side
+    /// cache.
+    ClientTrackingRefused,
+    /// Restoring a pub/sub subscription after a reconnect failed.
+    ResubscribeFailed {
+        /// The kind of subscription (exact, pattern, or sharded).
+        kind: PubSubSubscriptionKind,
+        /// The channel or pattern the subscription was for.
+        channel: Vec<u8>,
+        /// Whether the server never pushed a subscribe notification at all,
+        /// as opposed to pushing one that didn't match `kind`/`channel`.
+        no_notification_received: bool,
+    },
+}
+
+impl SetupError {
+    fn error_kind(&self) -> ErrorKind {
+        match self {
+            SetupError::AuthFailed => ErrorKind::AuthenticationFailed,
+            SetupError::SelectDbRefused
+            | SetupError::SetNameRefused
+            | SetupError::AzDiscoveryFailed
+            | SetupError::ClientTrackingRefused
+            | SetupError::ResubscribeFailed { .. } => ErrorKind::ResponseError,
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            SetupError::AuthFailed => "Password authentication failed",
+            SetupError::SelectDbRefused => "Redis server refused to switch database",
+            SetupError::SetNameRefused => "Redis server refused to set client name",
+            SetupError::AzDiscoveryFailed => "Failed to execute INFO command",
+            SetupError::ClientTrackingRefused => {
+                "Redis server refused to enable client-side caching"
+            }
+            SetupError::ResubscribeFailed {
+                no_notification_received: true,
+                ..
+            } => "Failed to receive subscription notification while restoring subscription channels",
+            SetupError::ResubscribeFailed { .. } => "Failed to restore subscription channels",
+        }
+    }
+
+    fn kind(&self) -> SetupErrorKind {
+        match self {
+            SetupError::AuthFailed => SetupErrorKind::AuthFailed,
+            SetupError::SelectDbRefused => SetupErrorKind::SelectDbRefused,
+            SetupError::SetNameRefused => SetupErrorKind::SetNameRefused,
+            SetupError::AzDiscoveryFailed => SetupErrorKind::AzDiscoveryFailed,
+            SetupError::ClientTrackingRefused => SetupErrorKind::ClientTrackingRefused,
+            SetupError::ResubscribeFailed { .. } => SetupErrorKind::ResubscribeFailed,
+        }
+    }
+
+    // A marker identifying this variant's `SetupErrorKind`, appended to
+    // every converted `RedisError`'s detail string so `classify` can recover
+    // it later. Kept out of `description()` so existing code that only
+    // matches on `ErrorKind`/the human-readable description is unaffected.
+    fn marker(&self) -> String {
+        format!("[setup_error={}]", self.kind().tag())
+    }
+
+    fn detail(&self) -> String {
+        match self {
+            SetupError::ResubscribeFailed { kind, channel, .. } => format!(
+                "{kind:?} subscription to {:?} {}",
+                String::from_utf8_lossy(channel),
+                self.marker()
+            ),
+            _ => self.marker(),
+        }
+    }
+
+    /// Recovers the [`SetupErrorKind`] `err` was converted from, if `err`
+    /// came from a `SetupError` via `From`. Returns `None` for any other
+    /// `RedisError`.
+    pub fn classify(err: &RedisError) -> Option<SetupErrorKind> {
+        let detail = err.detail()?;
+        let tag = detail.rsplit_once("[setup_error=")?.1.strip_suffix(']')?;
+        SetupErrorKind::from_tag(tag)
+    }
+}
+
+impl From<SetupError> for RedisError {
+    fn from(err: SetupError) -> RedisError {
+        let detail = err.detail();
+        RedisError::from((err.error_kind(), err.description(), detail))
+    }
+}
+
 // Helper function to extract and update availability zone from INFO command
 async fn update_az_from_info<C>(con: &mut C) -> RedisResult<()>
 where
@@ -128,18 +527,104 @@ where
             }
             Ok(())
         }
-        Err(e) => {
-            // Handle the error case for the INFO command
-            Err(RedisError::from((
-                ErrorKind::ResponseError,
-                "Failed to execute INFO command. ",
-                format!("{e:?}"),
-            )))
+        Err(e) => Err(RedisError::from((
+            SetupError::AzDiscoveryFailed.error_kind(),
+            SetupError::AzDiscoveryFailed.description(),
+            format!("{e:?} {}", SetupError::AzDiscoveryFailed.marker()),
+        ))),
+    }
+}
+
+// Setup commands other than HELLO that can be packed into a single
+// pipelined round-trip. Each entry remembers how to interpret its reply so
+// that batched and sequential execution fail in exactly the same way.
+enum PostHelloSetupStep<'a> {
+    Select(i64),
+    SetName(&'a str),
+    ClientTracking(&'a ClientSideCacheConfig),
+}
+
+impl PostHelloSetupStep<'_> {
+    fn to_cmd(&self) -> Cmd {
+        match self {
+            PostHelloSetupStep::Select(db) => {
+                let mut command = cmd("SELECT");
+                command.arg(*db);
+                command
+            }
+            PostHelloSetupStep::SetName(name) => {
+                let mut command = cmd("CLIENT");
+                command.arg("SETNAME").arg(*name);
+                command
+            }
+            PostHelloSetupStep::ClientTracking(config) => {
+                let mut command = cmd("CLIENT");
+                command.arg("TRACKING").arg("ON");
+                if let TrackingMode::Bcast { prefixes } = &config.mode {
+                    command.arg("BCAST");
+                    for prefix in prefixes {
+                        command.arg("PREFIX").arg(prefix);
+                    }
+                }
+                command
+            }
+        }
+    }
+
+    fn check_reply(&self, value: &Value) -> RedisResult<()> {
+        match (self, value) {
+            (PostHelloSetupStep::Select(_), Value::Okay) => Ok(()),
+            (PostHelloSetupStep::Select(_), _) => Err(SetupError::SelectDbRefused.into()),
+            (PostHelloSetupStep::SetName(_), Value::Okay) => Ok(()),
+            (PostHelloSetupStep::SetName(_), _) => Err(SetupError::SetNameRefused.into()),
+            (PostHelloSetupStep::ClientTracking(_), Value::Okay) => Ok(()),
+            (PostHelloSetupStep::ClientTracking(_), _) => {
+                Err(SetupError::ClientTrackingRefused.into())
+            }
+        }
+    }
+}
+
+// Runs the given setup steps. When there's more than one, they're packed into
+// a single `Pipeline` and sent with one `req_packed_commands` round-trip
+// instead of one `query_async` per step - this is the difference between one
+// RTT and N RTTs on a (re)connect. A single step is sent as-is, since there's
+// no latency to save and it keeps the common case (no SELECT, no CLIENT
+// SETNAME) free of pipeline bookkeeping.
+async fn run_post_hello_setup<C>(con: &mut C, steps: &[PostHelloSetupStep<'_>]) -> RedisResult<()>
+where
+    C: ConnectionLike,
+{
+    match steps {
+        [] => Ok(()),
+        [step] => {
+            let reply = step.to_cmd().query_async(con).await?;
+            step.check_reply(&reply)
+        }
+        steps => {
+            let mut pipeline = crate::Pipeline::new();
+            for step in steps {
+                pipeline.add_command(step.to_cmd());
+            }
+            let replies = con
+                .req_packed_commands(&pipeline, 0, steps.len(), None)
+                .await?;
+            for (step, reply) in steps.iter().zip(replies.iter()) {
+                step.check_reply(reply)?;
+            }
+            Ok(())
         }
     }
 }
 
 // Initial setup for every connection.
+//
+// Kept at the original 3-argument signature so the existing call sites in
+// `connection.rs`, `multiplexed_connection.rs` and `connection_manager.rs` -
+// none of which are part of this checkout, so they can't be updated here -
+// keep compiling unchanged. Callers that want the client-side cache and/or
+// lifecycle events added alongside this function should call
+// `setup_connection_with_observability` directly instead.
 async fn setup_connection<C>(
     connection_info: &RedisConnectionInfo,
     con: &mut C,
@@ -150,11 +635,42 @@ async fn setup_connection<C>(
 where
     C: ConnectionLike,
 {
+    setup_connection_with_observability(connection_info, con, discover_az, None, None).await
+}
+
+// As `setup_connection`, but also enables the RESP3 client-side cache and/or
+// reports lifecycle transitions - opt-in extensions that the existing call
+// sites above don't know about yet. A caller wiring up caching or a
+// [`ConnectionEventListener`] (e.g. `connection_manager.rs`'s reconnect loop)
+// should call this instead of `setup_connection` and pass real values.
+async fn setup_connection_with_observability<C>(
+    connection_info: &RedisConnectionInfo,
+    con: &mut C,
+    discover_az: bool,
+    // Present when the caller opted into the RESP3 client-side cache; enables
+    // `CLIENT TRACKING` during setup so invalidation pushes start flowing
+    // before any cacheable command is issued.
+    client_side_cache: Option<&ClientSideCacheConfig>,
+    // Present when the caller wants to observe lifecycle transitions (see
+    // [`ConnectionEventListener`]) instead of only learning about failures
+    // through the returned `RedisResult`.
+    event_listener: Option<&dyn ConnectionEventListener>,
+) -> RedisResult<()>
+where
+    C: ConnectionLike,
+{
+    if let Some(listener) = event_listener {
+        listener.on_event(ConnectionEvent::Connecting);
+    }
+
+    // AUTH is embedded in the RESP3 HELLO reply, and every step below depends
+    // on an authenticated session, so HELLO/AUTH always goes first and alone.
     if connection_info.protocol != ProtocolVersion::RESP2 {
         let hello_cmd = resp3_hello(connection_info);
         let val: RedisResult<Value> = hello_cmd.query_async(con).await;
         if let Err(err) = val {
-            return Err(get_resp3_hello_command_error(err));
+            let result = Err(get_resp3_hello_command_error(err));
+            return report_setup_failure(event_listener, SetupStage::Hello, result).await;
         }
     } else if let Some(password) = &connection_info.password {
         let mut command = cmd("AUTH");
@@ -170,59 +686,49 @@ where
                 ))?;
 
                 if !err_msg.contains("wrong number of arguments for 'auth' command") {
-                    fail!((
-                        ErrorKind::AuthenticationFailed,
-                        "Password authentication failed",
-                    ));
+                    let result = Err(RedisError::from(SetupError::AuthFailed));
+                    return report_setup_failure(event_listener, SetupStage::Hello, result).await;
                 }
 
                 let mut command = cmd("AUTH");
                 match command.arg(password).query_async(con).await {
                     Ok(Value::Okay) => (),
                     _ => {
-                        fail!((
-                            ErrorKind::AuthenticationFailed,
-                            "Password authentication failed"
-                        ));
+                        let result = Err(RedisError::from(SetupError::AuthFailed));
+                        return report_setup_failure(event_listener, SetupStage::Hello, result)
+                            .await;
                     }
                 }
             }
             _ => {
-                fail!((
-                    ErrorKind::AuthenticationFailed,
-                    "Password authentication failed"
-                ));
+                let result = Err(RedisError::from(SetupError::AuthFailed));
+                return report_setup_failure(event_listener, SetupStage::Hello, result).await;
             }
         }
     }
 
+    // SELECT, CLIENT SETNAME and CLIENT TRACKING don't depend on each other,
+    // so they're all eligible for the batched fast-path below.
+    let mut post_hello_steps = Vec::with_capacity(3);
     if connection_info.db != 0 {
-        match cmd("SELECT").arg(connection_info.db).query_async(con).await {
-            Ok(Value::Okay) => (),
-            _ => fail!((
-                ErrorKind::ResponseError,
-                "Redis server refused to switch database"
-            )),
-        }
+        post_hello_steps.push(PostHelloSetupStep::Select(connection_info.db));
     }
-
     if let Some(client_name) = &connection_info.client_name {
-        match cmd("CLIENT")
-            .arg("SETNAME")
-            .arg(client_name)
-            .query_async(con)
-            .await
-        {
-            Ok(Value::Okay) => {}
-            _ => fail!((
-                ErrorKind::ResponseError,
-                "Redis server refused to set client name"
-            )),
+        post_hello_steps.push(PostHelloSetupStep::SetName(client_name));
+    }
+    // CLIENT TRACKING's invalidation pushes are RESP3 push frames, so caching
+    // is only enabled once the connection has negotiated RESP3 via HELLO.
+    if connection_info.protocol == ProtocolVersion::RESP3 {
+        if let Some(config) = client_side_cache {
+            post_hello_steps.push(PostHelloSetupStep::ClientTracking(config));
         }
     }
+    let post_hello_result = run_post_hello_setup(con, &post_hello_steps).await;
+    report_setup_failure(event_listener, SetupStage::PostHelloSetup, post_hello_result).await?;
 
     if discover_az {
-        update_az_from_info(con).await?;
+        let az_result = update_az_from_info(con).await;
+        report_setup_failure(event_listener, SetupStage::AzDiscovery, az_result).await?;
     }
 
     // result is ignored, as per the command's instructions.
@@ -231,7 +737,22 @@ where
         .query_async(con)
         .await;
 
-    // resubscribe
+    let resubscribe_result: RedisResult<()> = resubscribe(connection_info, con).await;
+    report_setup_failure(event_listener, SetupStage::Resubscribe, resubscribe_result).await?;
+
+    if let Some(listener) = event_listener {
+        listener.on_event(ConnectionEvent::Connected);
+    }
+    Ok(())
+}
+
+// Restores pub/sub subscriptions after a (re)connect. Split out of
+// `setup_connection` so its several error exit points can be wrapped in one
+// place and reported through a single `SetupStage::Resubscribe` event.
+async fn resubscribe<C>(connection_info: &RedisConnectionInfo, con: &mut C) -> RedisResult<()>
+where
+    C: ConnectionLike,
+{
     if connection_info.protocol != ProtocolVersion::RESP3 {
         return Ok(());
     }
@@ -260,48 +781,36 @@ where
             // We will assume the configured time out is enough for the server to push the notifications.
             match subscribe_command.query_async(con).await {
                 Ok(Value::Push { kind, data }) => {
-                    match *subscription_kind {
+                    let restored = match *subscription_kind {
                         PubSubSubscriptionKind::Exact => {
-                            if kind != PushKind::Subscribe
-                                || Value::BulkString(channel_pattern.clone()) != data[0]
-                            {
-                                fail!((
-                                    ErrorKind::ResponseError,
-                                    // TODO: Consider printing the exact command
-                                    "Failed to restore Exact subscription channels"
-                                ));
-                            }
+                            kind == PushKind::Subscribe
+                                && Value::BulkString(channel_pattern.clone()) == data[0]
                         }
                         PubSubSubscriptionKind::Pattern => {
-                            if kind != PushKind::PSubscribe
-                                || Value::BulkString(channel_pattern.clone()) != data[0]
-                            {
-                                fail!((
-                                    ErrorKind::ResponseError,
-                                    // TODO: Consider printing the exact command
-                                    "Failed to restore Pattern subscription channels"
-                                ));
-                            }
+                            kind == PushKind::PSubscribe
+                                && Value::BulkString(channel_pattern.clone()) == data[0]
                         }
                         PubSubSubscriptionKind::Sharded => {
-                            if kind != PushKind::SSubscribe
-                                || Value::BulkString(channel_pattern.clone()) != data[0]
-                            {
-                                fail!((
-                                    ErrorKind::ResponseError,
-                                    // TODO: Consider printing the exact command
-                                    "Failed to restore Sharded subscription channels"
-                                ));
-                            }
+                            kind == PushKind::SSubscribe
+                                && Value::BulkString(channel_pattern.clone()) == data[0]
+                        }
+                    };
+                    if !restored {
+                        return Err(SetupError::ResubscribeFailed {
+                            kind: *subscription_kind,
+                            channel: channel_pattern.clone(),
+                            no_notification_received: false,
                         }
+                        .into());
                     }
                 }
                 _ => {
-                    fail!((
-                        ErrorKind::ResponseError,
-                        // TODO: Consider printing the exact command
-                        "Failed to receive subscription notification while restoring subscription channels"
-                    ));
+                    return Err(SetupError::ResubscribeFailed {
+                        kind: *subscription_kind,
+                        channel: channel_pattern.clone(),
+                        no_notification_received: true,
+                    }
+                    .into());
                 }
             }
         }
@@ -310,6 +819,39 @@ where
     Ok(())
 }
 
+/// Interprets a RESP3 push frame, returning the set of keys a `CLIENT
+/// TRACKING` `invalidate` push is reporting stale - `None` for an
+/// `invalidate-all` (flush) notification, or `None` if `kind` isn't an
+/// `invalidate` push at all, as signalled by the outer `Option`.
+///
+/// [`ClientSideCache::apply_push`] wraps this together with
+/// [`ClientSideCache::handle_invalidation`] into the one call a push-frame
+/// receive loop needs to make. The multiplexed connection's loop (which
+/// already demultiplexes pub/sub pushes) is the intended caller, but
+/// `aio/multiplexed_connection.rs` isn't part of this checkout, so that
+/// wiring can't be added here.
+pub(crate) fn invalidated_keys_from_push(
+    kind: &PushKind,
+    data: &[Value],
+) -> Option<Option<Vec<Vec<u8>>>> {
+    if *kind != PushKind::Invalidate {
+        return None;
+    }
+    Some(match data.first() {
+        Some(Value::Array(keys)) => Some(
+            keys.iter()
+                .filter_map(|key| match key {
+                    Value::BulkString(key) => Some(key.clone()),
+                    _ => None,
+                })
+                .collect(),
+        ),
+        _ => None,
+    })
+}
+
+mod caching;
+pub use caching::*;
 mod connection;
 pub use connection::*;
 mod multiplexed_connection;